@@ -13,6 +13,7 @@ mod term;
 
 mod cli;
 mod manifest;
+mod message_format;
 mod metadata;
 mod package;
 mod process;
@@ -25,6 +26,7 @@ use std::{env, ffi::OsString, fs, path::Path};
 use crate::{
     cli::{Args, Coloring},
     manifest::{find_root_manifest_for_wd, Manifest},
+    message_format::SummaryRecord,
     metadata::Metadata,
     package::{Kind, Package, Progress},
     process::ProcessBuilder,
@@ -116,9 +118,18 @@ fn exec_on_workspace(
         Package::from_iter(args, package, &mut progress)?
     };
 
-    packages
-        .iter()
-        .try_for_each(|package| exec_on_package(args, package, &line, &restore, &mut progress))
+    let mut summary = SummaryRecord::default();
+    let result = packages.iter().try_for_each(|package| {
+        exec_on_package(args, package, &line, &restore, &mut progress, &mut summary)
+    });
+
+    // Emit the summary even when a package failed and `result` is about to
+    // propagate that error: the aggregate counts are the whole point of
+    // `--log-format=json` for CI, and are needed most on a failing run.
+    if args.message_format.is_json() {
+        summary.emit();
+    }
+    result
 }
 
 fn exec_on_package(
@@ -127,6 +138,7 @@ fn exec_on_package(
     line: &ProcessBuilder<'_>,
     restore: &Restore,
     progress: &mut Progress,
+    summary: &mut SummaryRecord,
 ) -> Result<()> {
     if let Kind::SkipAsPrivate = package.kind {
         info!(args.color, "skipped running on private crate {}", package.name_verbose(args));
@@ -138,7 +150,7 @@ fn exec_on_package(
         line.arg("--manifest-path");
         line.arg(&package.manifest_path);
 
-        no_dev_deps(args, package, &mut line, restore, progress)
+        no_dev_deps(args, package, &mut line, restore, progress, summary)
     }
 }
 
@@ -148,6 +160,7 @@ fn no_dev_deps(
     line: &mut ProcessBuilder<'_>,
     restore: &Restore,
     progress: &mut Progress,
+    summary: &mut SummaryRecord,
 ) -> Result<()> {
     if args.no_dev_deps || args.remove_dev_deps {
         let new = package.manifest.remove_dev_deps();
@@ -157,11 +170,11 @@ fn no_dev_deps(
             format!("failed to update manifest file: {}", package.manifest_path.display())
         })?;
 
-        package::exec(args, package, line, progress)?;
+        package::exec(args, package, line, progress, summary)?;
 
         handle.done()
     } else {
-        package::exec(args, package, line, progress)
+        package::exec(args, package, line, progress, summary)
     }
 }
 
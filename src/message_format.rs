@@ -0,0 +1,196 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::{bail, Error};
+
+/// Output format for cargo-hack's own per-invocation and summary records.
+///
+/// This is selected with `--log-format`, a cargo-hack-only flag, not
+/// `--message-format`: cargo-hack already forwards an unrecognized
+/// `--message-format` straight through to the underlying `cargo build`/
+/// `cargo test` invocation (it controls the *compiler's* diagnostic
+/// output), so reusing that name for cargo-hack's own reporting would
+/// silently change what existing `--message-format=json` invocations do.
+///
+/// `Human` preserves the existing `term`-macro based output. `Json` makes
+/// cargo-hack emit one JSON object per underlying `cargo` invocation to
+/// stdout, followed by a final aggregate summary record, so that CI
+/// systems and wrapper tools don't have to scrape human-readable text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MessageFormat {
+    Human,
+    Json,
+}
+
+impl Default for MessageFormat {
+    fn default() -> Self {
+        MessageFormat::Human
+    }
+}
+
+impl MessageFormat {
+    pub(crate) fn is_json(self) -> bool {
+        matches!(self, MessageFormat::Json)
+    }
+}
+
+impl FromStr for MessageFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            other => bail!(
+                "argument for --log-format must be `human` or `json`, but found `{}`",
+                other
+            ),
+        }
+    }
+}
+
+impl fmt::Display for MessageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MessageFormat::Human => "human",
+            MessageFormat::Json => "json",
+        })
+    }
+}
+
+/// One record per underlying `cargo` invocation, emitted as a single line
+/// of JSON to stdout when `MessageFormat::Json` is selected.
+///
+/// This intentionally sticks to hand-rolled JSON rather than pulling in
+/// `serde_json` so the new flag doesn't grow cargo-hack's dependency tree
+/// just to print a few fields.
+pub(crate) struct InvocationRecord<'a> {
+    pub(crate) package: &'a str,
+    pub(crate) manifest_path: &'a str,
+    pub(crate) features: &'a [String],
+    pub(crate) argv: &'a [String],
+    pub(crate) success: bool,
+    pub(crate) elapsed: Duration,
+}
+
+impl InvocationRecord<'_> {
+    pub(crate) fn emit(&self) {
+        println!(
+            r#"{{"reason":"cargo-hack-run","package":{},"manifest_path":{},"features":[{}],"argv":[{}],"success":{},"elapsed_secs":{}}}"#,
+            json_str(self.package),
+            json_str(self.manifest_path),
+            join_json_str(self.features),
+            join_json_str(self.argv),
+            self.success,
+            self.elapsed.as_secs_f64(),
+        );
+    }
+}
+
+/// Final aggregate record, emitted once at the end of the run, including
+/// when a failing package stops the run before the remaining packages.
+#[derive(Default)]
+pub(crate) struct SummaryRecord {
+    pub(crate) total: usize,
+    pub(crate) passed: usize,
+    pub(crate) failed: usize,
+}
+
+impl SummaryRecord {
+    pub(crate) fn record(&mut self, success: bool) {
+        self.total += 1;
+        if success {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+
+    pub(crate) fn emit(&self) {
+        println!(
+            r#"{{"reason":"cargo-hack-summary","total":{},"passed":{},"failed":{}}}"#,
+            self.total, self.passed, self.failed,
+        );
+    }
+}
+
+/// Escapes `s` as a JSON string literal, including quotes.
+///
+/// Covers the full set of characters JSON requires escaping (`"`, `\`,
+/// and all C0 control characters, via `\uXXXX` for anything without a
+/// short escape), since manifest paths, feature names, and argv entries
+/// can plausibly contain tabs, carriage returns, or other control bytes
+/// (e.g. `\r\n` line endings on Windows, or shell-quoted arguments).
+fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn join_json_str(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|s| json_str(s))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_format_from_str() {
+        assert_eq!(
+            MessageFormat::from_str("human").unwrap(),
+            MessageFormat::Human
+        );
+        assert_eq!(
+            MessageFormat::from_str("json").unwrap(),
+            MessageFormat::Json
+        );
+        assert!(MessageFormat::from_str("xml").is_err());
+    }
+
+    #[test]
+    fn message_format_default_is_human() {
+        assert_eq!(MessageFormat::default(), MessageFormat::Human);
+    }
+
+    #[test]
+    fn json_str_escapes_quotes_and_backslashes() {
+        assert_eq!(json_str(r#"a"b\c"#), r#""a\"b\\c""#);
+    }
+
+    #[test]
+    fn json_str_escapes_control_characters() {
+        assert_eq!(json_str("a\tb\r\nc"), r#""a\tb\r\nc""#);
+        assert_eq!(json_str("\u{1}"), r#""\u0001""#);
+    }
+
+    #[test]
+    fn json_str_passes_through_plain_text() {
+        assert_eq!(json_str("serde_json-free"), r#""serde_json-free""#);
+    }
+
+    #[test]
+    fn join_json_str_joins_multiple_entries() {
+        assert_eq!(
+            join_json_str(&["a".to_owned(), "b\"c".to_owned()]),
+            r#""a","b\"c""#
+        );
+    }
+}